@@ -0,0 +1,380 @@
+use regex::Regex;
+
+/// A single `$name=value` attached to a node label.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variable {
+    pub name: String,
+    pub value: VariableValue,
+}
+
+impl Variable {
+    pub fn new(name: impl Into<String>, value: VariableValue) -> Self {
+        Variable {
+            name: name.into(),
+            value,
+        }
+    }
+
+    pub fn boolean(name: impl Into<String>, value: bool) -> Self {
+        Variable::new(name, VariableValue::boolean(value))
+    }
+
+    /// A regex matching a single `$name=value` pair, for callers that only
+    /// need to know whether a string looks like a variable. Mirrors the
+    /// value grammar [`parse_value`] accepts, so this and [`Variable::parse`]
+    /// agree with the label tokenizer on what counts as a variable.
+    pub fn variable_rx() -> Regex {
+        Regex::new(r#"\$[A-Za-z][A-Za-z0-9_-]*=("(?:[^"\\]|\\["\\])*"|[A-Za-z0-9_:-]+)"#)
+            .expect("not a regex")
+    }
+
+    /// Parses the first `$name=value` pair found in `input`, if any.
+    pub fn parse(input: &str) -> Option<Self> {
+        input.match_indices('$').find_map(|(dollar, _)| {
+            let rest = &input[dollar + 1..];
+
+            let name_end = rest
+                .char_indices()
+                .find(|(i, c)| {
+                    if *i == 0 {
+                        !c.is_ascii_alphabetic()
+                    } else {
+                        !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+                    }
+                })
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            if name_end == 0 {
+                return None;
+            }
+            let name = &rest[..name_end];
+
+            let after_eq = rest[name_end..].strip_prefix('=')?;
+            let (_, value) = parse_value(after_eq)?;
+
+            Some(Variable::new(name, VariableValue::infer(&value)))
+        })
+    }
+}
+
+/// Parses a variable value from the start of `input`: either a bareword
+/// (the usual `[A-Za-z0-9_:-]+` charset, `:` admitted for date-time
+/// values) or a double-quoted string with `\"`/`\\` escapes. Returns the
+/// raw text consumed (including quotes, if any) alongside the unescaped
+/// value.
+///
+/// This is the single source of truth for the value grammar, used by
+/// [`Variable::parse`] and the label tokenizer in `labels` and mirrored by
+/// the regex in [`Variable::variable_rx`], so they can't disagree on what a
+/// variable value looks like.
+pub(crate) fn parse_value(input: &str) -> Option<(String, String)> {
+    if let Some(rest) = input.strip_prefix('"') {
+        let mut value = String::new();
+        let mut tail = rest;
+        loop {
+            match tail.chars().next() {
+                None => return None,
+                Some('"') => {
+                    tail = &tail[1..];
+                    break;
+                }
+                Some('\\') => {
+                    let after = &tail[1..];
+                    match after.chars().next() {
+                        Some(escaped @ ('"' | '\\')) => {
+                            value.push(escaped);
+                            tail = &after[escaped.len_utf8()..];
+                        }
+                        _ => return None,
+                    }
+                }
+                Some(c) => {
+                    value.push(c);
+                    tail = &tail[c.len_utf8()..];
+                }
+            }
+        }
+        let raw = input[..input.len() - tail.len()].to_string();
+        Some((raw, value))
+    } else {
+        let end = input
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-' || *c == ':'))
+            .map(|(i, _)| i)
+            .unwrap_or(input.len());
+        if end == 0 {
+            return None;
+        }
+        let raw = input[..end].to_string();
+        Some((raw.clone(), raw))
+    }
+}
+
+/// A (possibly compound) duration parsed from a `$name=<n><unit>...`
+/// variable, e.g. `4d`, `2w3d`, or `1y6M`.
+///
+/// Months and years aren't a fixed number of seconds, so the calendar part
+/// is kept separate from the fixed part rather than collapsing everything
+/// into one scalar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Duration {
+    /// The fixed part, accumulated from `s`/`m`/`h`/`d`/`w` tokens.
+    pub seconds: u64,
+    /// The calendar part, accumulated from `M`/`y` tokens (`months + 12 * years`).
+    pub months: u32,
+}
+
+impl Duration {
+    pub fn seconds(n: u64) -> Self {
+        Duration { seconds: n, months: 0 }
+    }
+
+    pub fn minutes(n: u64) -> Self {
+        Duration::seconds(n * 60)
+    }
+
+    pub fn hours(n: u64) -> Self {
+        Duration::seconds(n * 60 * 60)
+    }
+
+    pub fn days(n: u64) -> Self {
+        Duration::seconds(n * 60 * 60 * 24)
+    }
+
+    pub fn weeks(n: u64) -> Self {
+        Duration::seconds(n * 60 * 60 * 24 * 7)
+    }
+
+    pub fn months(n: u32) -> Self {
+        Duration { seconds: 0, months: n }
+    }
+
+    pub fn years(n: u32) -> Self {
+        Duration::months(n * 12)
+    }
+}
+
+/// An absolute calendar date parsed from a `$name=YYYY-MM-DD` (optionally
+/// `YYYY-MM-DDThh:mm`) variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: Option<u8>,
+    pub minute: Option<u8>,
+}
+
+/// The inferred type of a variable's value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableValue {
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Time(Duration),
+    Date(Date),
+}
+
+impl Eq for VariableValue {}
+
+impl std::hash::Hash for VariableValue {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        match self {
+            VariableValue::String(s) => {
+                0u8.hash(state);
+                s.hash(state);
+            }
+            VariableValue::Number(n) => {
+                1u8.hash(state);
+                n.to_bits().hash(state);
+            }
+            VariableValue::Boolean(b) => {
+                2u8.hash(state);
+                b.hash(state);
+            }
+            VariableValue::Time(t) => {
+                3u8.hash(state);
+                t.hash(state);
+            }
+            VariableValue::Date(d) => {
+                4u8.hash(state);
+                d.hash(state);
+            }
+        }
+    }
+}
+
+impl VariableValue {
+    pub fn string(value: impl Into<String>) -> Self {
+        VariableValue::String(value.into())
+    }
+
+    pub fn number(value: f64) -> Self {
+        VariableValue::Number(value)
+    }
+
+    pub fn boolean(value: bool) -> Self {
+        VariableValue::Boolean(value)
+    }
+
+    pub fn time(value: Duration) -> Self {
+        VariableValue::Time(value)
+    }
+
+    pub fn date(value: Date) -> Self {
+        VariableValue::Date(value)
+    }
+
+    /// Infers the type of a variable's value from its textual form,
+    /// trying boolean, then number, then duration, then an absolute date,
+    /// and finally falling back to a plain string.
+    pub fn infer(value: &str) -> Self {
+        if let Ok(b) = value.parse::<bool>() {
+            return VariableValue::boolean(b);
+        }
+        if let Ok(n) = value.parse::<f64>() {
+            return VariableValue::number(n);
+        }
+        if let Some(duration) = parse_duration(value) {
+            return VariableValue::time(duration);
+        }
+        if let Some(date) = parse_date(value) {
+            return VariableValue::date(date);
+        }
+        VariableValue::string(value)
+    }
+}
+
+/// Parses `YYYY-MM-DD`, optionally followed by `Thh:mm`, rejecting
+/// impossible dates (month 0 or >12, or a day out of range for the month,
+/// accounting for leap years).
+fn parse_date(value: &str) -> Option<Date> {
+    let (date_part, time_part) = match value.split_once('T') {
+        Some((date_part, time_part)) => (date_part, Some(time_part)),
+        None => (value, None),
+    };
+
+    let mut parts = date_part.split('-');
+    let year_str = parts.next()?;
+    let month_str = parts.next()?;
+    let day_str = parts.next()?;
+    if parts.next().is_some()
+        || year_str.len() != 4
+        || month_str.len() != 2
+        || day_str.len() != 2
+        || !date_part.chars().all(|c| c.is_ascii_digit() || c == '-')
+    {
+        return None;
+    }
+    let year: u16 = year_str.parse().ok()?;
+    let month: u8 = month_str.parse().ok()?;
+    let day: u8 = day_str.parse().ok()?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let (hour, minute) = match time_part {
+        Some(time_part) => {
+            let mut parts = time_part.split(':');
+            let hour_str = parts.next()?;
+            let minute_str = parts.next()?;
+            if parts.next().is_some()
+                || hour_str.len() != 2
+                || minute_str.len() != 2
+                || !time_part.chars().all(|c| c.is_ascii_digit() || c == ':')
+            {
+                return None;
+            }
+            let hour: u8 = hour_str.parse().ok()?;
+            let minute: u8 = minute_str.parse().ok()?;
+            if hour > 23 || minute > 59 {
+                return None;
+            }
+            (Some(hour), Some(minute))
+        }
+        None => (None, None),
+    };
+
+    Some(Date {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+    })
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+/// Parses a compound duration such as `2w3d4h30m`: repeatedly reads an
+/// unsigned integer followed by a one-character unit and accumulates the
+/// result, rejecting a repeated unit or any leftover characters. `m` is
+/// minutes and `M` is months; `s`/`h`/`w`/`d`/`y` round out the rest.
+fn parse_duration(value: &str) -> Option<Duration> {
+    if value.is_empty() {
+        return None;
+    }
+
+    let mut duration = Duration::default();
+    let mut seen_units = Vec::new();
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let digits_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits_end == 0 {
+            return None;
+        }
+        let (digits, after_digits) = rest.split_at(digits_end);
+        let n: u64 = digits.parse().ok()?;
+
+        let mut chars = after_digits.chars();
+        let unit = chars.next()?;
+        if seen_units.contains(&unit) {
+            return None;
+        }
+        seen_units.push(unit);
+
+        let seconds_per_unit: Option<u64> = match unit {
+            's' => Some(1),
+            'm' => Some(60),
+            'h' => Some(60 * 60),
+            'd' => Some(60 * 60 * 24),
+            'w' => Some(60 * 60 * 24 * 7),
+            _ => None,
+        };
+        let months_per_unit: Option<u32> = match unit {
+            'M' => Some(1),
+            'y' => Some(12),
+            _ => None,
+        };
+
+        if let Some(per_unit) = seconds_per_unit {
+            let added = n.checked_mul(per_unit)?;
+            duration.seconds = duration.seconds.checked_add(added)?;
+        } else if let Some(per_unit) = months_per_unit {
+            let n: u32 = n.try_into().ok()?;
+            let added = n.checked_mul(per_unit)?;
+            duration.months = duration.months.checked_add(added)?;
+        } else {
+            return None;
+        }
+
+        rest = chars.as_str();
+    }
+
+    Some(duration)
+}
@@ -0,0 +1,20 @@
+use std::fmt;
+
+/// A `#tag` lifted out of a node label.
+///
+/// Stored without any leading/trailing whitespace; the leading `#` is kept
+/// as part of the value so it round-trips straight back into a label.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HashTag(String);
+
+impl HashTag {
+    pub fn new(tag: impl Into<String>) -> Self {
+        HashTag(tag.into().trim().to_string())
+    }
+}
+
+impl fmt::Display for HashTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -1,8 +1,9 @@
 use crate::graph::{Variable, VariableValue};
 use crate::hash::HashTag;
 use crate::Label;
-use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::Path;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct NodeInfo {
@@ -24,20 +25,40 @@ impl NodeInfo {
     }
 
     pub fn parse(label: &Label) -> Self {
-        let base_label = &label.to_string();
+        Self::parse_with_tags(label, &TagConfig::default())
+    }
+
+    /// Like [`NodeInfo::parse`], but canonicalizes hashtags (aliasing and
+    /// dropping stoplisted ones) through `tag_config` first.
+    pub fn parse_with_tags(label: &Label, tag_config: &TagConfig) -> Self {
+        let base_label = label.to_string();
+        let segments = tokenizer::tokenize(&base_label);
 
-        let (tags, label) = extract_hashtags(base_label);
-        let (variables, label) = extract_variables(label);
+        let mut tags = HashSet::new();
+        let mut subgraph = None;
+        let mut variables = HashSet::new();
+        for segment in &segments {
+            match segment {
+                tokenizer::Segment::Tag(tag) => {
+                    if let Some(canonical) = tag_config.canonicalize(tag) {
+                        tags.insert(HashTag::new(canonical));
+                    }
+                }
+                tokenizer::Segment::Subgraph(tag) => {
+                    subgraph = tag_config.canonicalize(tag).map(HashTag::new);
+                }
+                tokenizer::Segment::Var(name, value, _) => {
+                    variables.insert(Variable::new(name.clone(), VariableValue::infer(value)));
+                }
+                tokenizer::Segment::Text(_) => {}
+            }
+        }
 
-        let subgraph: Option<HashTag> = tags
-            .iter()
-            .find(|t| t.to_string().starts_with("#SG_"))
-            .cloned();
+        let mut tags: Vec<_> = tags.into_iter().collect();
+        tags.sort();
 
-        let tags: Vec<_> = tags
-            .into_iter()
-            .filter(|t| !t.to_string().starts_with("#SG_"))
-            .collect();
+        let label = render_label(&segments);
+        let variables = variables.into_iter().collect();
 
         NodeInfo {
             label,
@@ -48,55 +69,209 @@ impl NodeInfo {
     }
 }
 
-fn extract_variables(input: impl AsRef<str>) -> (Vec<Variable>, String) {
-    let input = input.as_ref();
-    let rx = Regex::new("\\$([A-Za-z][A-Za-z0-9_-]*)=([A-Za-z0-9_-]+)").expect("not a regex");
-    let mut variables = HashSet::new();
-    for (_, [name, value]) in rx.captures_iter(input).map(|c| c.extract()) {
-        let variable_value = VariableValue::infer(value);
-        let variable = Variable::new(name, variable_value);
-        variables.insert(variable);
+/// An optional alias-and-stoplist mapping for hashtags, so that
+/// near-duplicates like `#TODO`/`#todo`/`#to-do` can be folded down to one
+/// canonical tag and noise tags can be dropped entirely.
+///
+/// Loaded from a line-oriented file: `#`-prefixed lines are comments, an
+/// `alias = canonical` line maps `alias` to `canonical`, and any other
+/// non-empty line is a stoplisted tag. Matching against both maps is
+/// case-insensitive and ignores a leading `#`.
+#[derive(Debug, Clone, Default)]
+pub struct TagConfig {
+    aliases: HashMap<String, String>,
+    stop_tags: HashSet<String>,
+}
+
+impl TagConfig {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&contents))
     }
 
-    let variables = variables.into_iter().collect();
-    (variables, input.to_string())
-}
+    pub fn parse(input: &str) -> Self {
+        let mut aliases = HashMap::new();
+        let mut stop_tags = HashSet::new();
 
-fn extract_hashtags(input: impl AsRef<str>) -> (Vec<HashTag>, String) {
-    let input = input.as_ref();
-    let rx = Regex::new("#[A-Za-z][A-Za-z0-9_-]*").expect("not a regex");
-    let mut hashes = HashSet::new();
-    for hash in rx.captures_iter(input) {
-        let hash = hash.get(0).unwrap().as_str().to_string();
-        hashes.insert(hash);
-    }
-
-    // trim any trailing hashtags, since they'll be immediately displayed underneath.
-    let mut work_done = true;
-    let mut new_label = input.to_string();
-
-    while work_done {
-        new_label = new_label.trim().to_string();
-        work_done = false;
-        for hash in hashes.iter() {
-            if new_label.ends_with(hash) {
-                let split_at = new_label.len() - hash.len();
-                new_label = new_label[..split_at].to_string();
-                work_done = true;
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((alias, canonical)) => {
+                    aliases.insert(normalize_tag(alias.trim()), strip_hash(canonical.trim()));
+                }
+                None => {
+                    stop_tags.insert(normalize_tag(line));
+                }
             }
         }
+
+        TagConfig { aliases, stop_tags }
+    }
+
+    /// Resolves a raw `#tag` (with its leading `#`) to its canonical form,
+    /// or `None` if it's stoplisted.
+    fn canonicalize(&self, tag: &str) -> Option<String> {
+        let key = normalize_tag(tag);
+        if self.stop_tags.contains(&key) {
+            return None;
+        }
+        match self.aliases.get(&key) {
+            Some(canonical) => Some(format!("#{}", canonical)),
+            None => Some(tag.to_string()),
+        }
     }
-    let mut hashes: Vec<_> = hashes.into_iter().collect();
-    hashes.sort();
+}
+
+fn strip_hash(tag: &str) -> String {
+    tag.trim_start_matches('#').to_string()
+}
 
-    let hashtags = hashes.into_iter().map(HashTag::new).collect();
-    (hashtags, new_label)
+fn normalize_tag(tag: &str) -> String {
+    strip_hash(tag).to_lowercase()
+}
+
+/// Renders the trimmed label text from a token stream: trailing tag and
+/// subgraph segments are dropped (along with the whitespace that
+/// separated them from the rest of the label) since they'll be displayed
+/// immediately underneath it, mirroring the old `ends_with` trimming but
+/// driven off segment positions instead of repeated substring matching.
+fn render_label(segments: &[tokenizer::Segment]) -> String {
+    let mut end = segments.len();
+    while end > 0 {
+        match &segments[end - 1] {
+            tokenizer::Segment::Tag(_) | tokenizer::Segment::Subgraph(_) => end -= 1,
+            tokenizer::Segment::Text(text) if text.trim().is_empty() => end -= 1,
+            _ => break,
+        }
+    }
+
+    let mut label = String::new();
+    for segment in &segments[..end] {
+        match segment {
+            tokenizer::Segment::Text(text) => label.push_str(text),
+            tokenizer::Segment::Tag(tag) => label.push_str(tag),
+            tokenizer::Segment::Subgraph(tag) => label.push_str(tag),
+            tokenizer::Segment::Var(_, _, raw) => label.push_str(raw),
+        }
+    }
+    label.trim().to_string()
+}
+
+/// A single-pass, parser-combinator tokenizer for node labels.
+///
+/// Labels mix plain text with three kinds of markup: `#hashtag`s,
+/// `#SG_...` subgraph tags, and `$name=value` variables. Walking the
+/// label once and emitting an ordered stream of segments (rather than
+/// running independent regex passes for tags and variables) keeps the
+/// position of each piece of markup relative to the others, which lets
+/// callers make trimming/rendering decisions without re-scanning the
+/// original string.
+mod tokenizer {
+    use nom::{
+        branch::alt,
+        bytes::complete::take_while1,
+        character::complete::{anychar, char},
+        combinator::{map, recognize, verify},
+        multi::many0,
+        sequence::pair,
+        IResult,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(super) enum Segment {
+        /// Plain label text.
+        Text(String),
+        /// A `#hashtag`, stored with its leading `#`.
+        Tag(String),
+        /// A `#SG_...` subgraph tag, stored with its leading `#`.
+        Subgraph(String),
+        /// A `$name=value` variable: name, unescaped value, and the raw
+        /// `$name=value` text it was parsed from (so the label can be
+        /// rendered back out unchanged, matching today's behaviour of
+        /// leaving variables in place).
+        Var(String, String, String),
+    }
+
+    pub(super) fn tokenize(input: &str) -> Vec<Segment> {
+        let (_, segments) =
+            many0(alt((variable, hashtag, text)))(input).expect("tokenizer is infallible");
+        segments
+    }
+
+    fn identifier(input: &str) -> IResult<&str, &str> {
+        recognize(pair(
+            verify(anychar, |c: &char| c.is_ascii_alphabetic()),
+            take_while1_or_empty(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        ))(input)
+    }
+
+    fn take_while1_or_empty(
+        predicate: impl Fn(char) -> bool,
+    ) -> impl Fn(&str) -> IResult<&str, &str> {
+        move |input: &str| {
+            let end = input
+                .char_indices()
+                .find(|(_, c)| !predicate(*c))
+                .map(|(i, _)| i)
+                .unwrap_or(input.len());
+            Ok((&input[end..], &input[..end]))
+        }
+    }
+
+    /// A variable value, delegating to [`crate::graph::parse_value`] for
+    /// the actual grammar (bareword or double-quoted-with-escapes) so this
+    /// tokenizer and `Variable::parse`/`Variable::variable_rx` can't
+    /// disagree on what a variable value looks like.
+    fn variable_value(input: &str) -> IResult<&str, (String, String)> {
+        crate::graph::parse_value(input)
+            .map(|(raw, value)| {
+                let rest = &input[raw.len()..];
+                (rest, (raw, value))
+            })
+            .ok_or_else(|| {
+                nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify))
+            })
+    }
+
+    fn variable(input: &str) -> IResult<&str, Segment> {
+        let (input, _) = char('$')(input)?;
+        let (input, name) = identifier(input)?;
+        let (input, _) = char('=')(input)?;
+        let (input, (raw_value, value)) = variable_value(input)?;
+        let raw = format!("${}={}", name, raw_value);
+        Ok((input, Segment::Var(name.to_string(), value, raw)))
+    }
+
+    fn hashtag(input: &str) -> IResult<&str, Segment> {
+        let (input, _) = char('#')(input)?;
+        let (input, name) = identifier(input)?;
+        let tag = format!("#{}", name);
+        if tag.starts_with("#SG_") {
+            Ok((input, Segment::Subgraph(tag)))
+        } else {
+            Ok((input, Segment::Tag(tag)))
+        }
+    }
+
+    /// Anything that isn't a tag or a variable, including a lone `#` or
+    /// `$` that didn't form one (so the tokenizer always makes progress).
+    fn text(input: &str) -> IResult<&str, Segment> {
+        alt((
+            map(take_while1(|c: char| c != '#' && c != '$'), |s: &str| {
+                Segment::Text(s.to_string())
+            }),
+            map(anychar, |c: char| Segment::Text(c.to_string())),
+        ))(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::Time;
+    use crate::graph::Duration;
 
     #[test]
     fn it_matches_variables() {
@@ -115,19 +290,61 @@ mod tests {
             ),
             (
                 "$delay=4d",
-                Variable::new("delay", VariableValue::time(Time::Day(4))),
+                Variable::new("delay", VariableValue::time(Duration::days(4))),
             ),
             (
                 "$delay=4m",
-                Variable::new("delay", VariableValue::time(Time::Minute(4))),
+                Variable::new("delay", VariableValue::time(Duration::minutes(4))),
             ),
             (
                 "$delay=4M",
-                Variable::new("delay", VariableValue::time(Time::Month(4))),
+                Variable::new("delay", VariableValue::time(Duration::months(4))),
             ),
             (
                 "$delay=4y",
-                Variable::new("delay", VariableValue::time(Time::Year(4))),
+                Variable::new("delay", VariableValue::time(Duration::years(4))),
+            ),
+            (
+                "$delay=2w3d4h30m",
+                Variable::new(
+                    "delay",
+                    VariableValue::time(Duration {
+                        seconds: 2 * Duration::weeks(1).seconds
+                            + 3 * Duration::days(1).seconds
+                            + 4 * Duration::hours(1).seconds
+                            + 30 * Duration::minutes(1).seconds,
+                        months: 0,
+                    }),
+                ),
+            ),
+            (
+                "$delay=1y6M",
+                Variable::new("delay", VariableValue::time(Duration::months(18))),
+            ),
+            (
+                "$delay=4d4d",
+                Variable::new("delay", VariableValue::string("4d4d")),
+            ),
+            (
+                "$delay=4000000000y",
+                Variable::new("delay", VariableValue::string("4000000000y")),
+            ),
+            (
+                "$owner=\"Jane Doe\"",
+                Variable::new("owner", VariableValue::string("Jane Doe")),
+            ),
+            (
+                "$deadline=2026-08-01T09:30",
+                Variable::new(
+                    "deadline",
+                    VariableValue::date(crate::graph::Date {
+                        year: 2026,
+                        month: 8,
+                        day: 1,
+                        hour: Some(9),
+                        minute: Some(30),
+                    }),
+                ),
             ),
         ];
 
@@ -192,11 +409,146 @@ mod tests {
     fn it_parses_node_label_with_boolean_variable() {
         let actual = NodeInfo::parse(&Label("positive choice $choice=true".to_string()));
         let expected = NodeInfo {
-            label: "a positive choice".to_string(),
+            label: "positive choice $choice=true".to_string(),
             tags: vec![],
             variables: vec![Variable::boolean("choice", true)],
             subgraph: None,
         };
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn it_parses_quoted_variable_values() {
+        let actual = NodeInfo::parse(&Label("meet #boss $owner=\"Jane Doe\"".to_string()));
+        let expected = NodeInfo {
+            label: "meet #boss $owner=\"Jane Doe\"".to_string(),
+            tags: vec![HashTag::new("#boss")],
+            variables: vec![Variable::new("owner", VariableValue::string("Jane Doe"))],
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn it_parses_quoted_variable_values_with_escapes_and_literal_markup() {
+        let actual = NodeInfo::parse(&Label(
+            "note $label=\"first: second #not-a-tag \\\"quoted\\\" \\\\ done\"".to_string(),
+        ));
+        let expected = NodeInfo {
+            label: "note $label=\"first: second #not-a-tag \\\"quoted\\\" \\\\ done\""
+                .to_string(),
+            tags: vec![],
+            variables: vec![Variable::new(
+                "label",
+                VariableValue::string("first: second #not-a-tag \"quoted\" \\ done"),
+            )],
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn it_canonicalizes_aliased_tags_and_drops_stoplisted_ones() {
+        let config = TagConfig::parse(
+            "# canonical tag spellings\nto-do = TODO\ntodo = TODO\n\n# noise\nwip\n",
+        );
+        let actual = NodeInfo::parse_with_tags(
+            &Label("a #to-do and a #wip and a #keeper".to_string()),
+            &config,
+        );
+        let expected = NodeInfo {
+            label: "a #to-do and a #wip and a".to_string(),
+            tags: vec![HashTag::new("#TODO"), HashTag::new("#keeper")],
+            variables: Vec::new(),
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn it_drops_stoplisted_subgraph_tags() {
+        let config = TagConfig::parse("sg_draft\n");
+        let actual = NodeInfo::parse_with_tags(&Label("foo #SG_draft".to_string()), &config);
+        let expected = NodeInfo {
+            label: "foo".to_string(),
+            tags: vec![],
+            variables: Vec::new(),
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn it_infers_date_variables() {
+        use crate::graph::Date;
+
+        let actual = NodeInfo::parse(&Label("ship it $deadline=2026-08-01".to_string()));
+        let expected = NodeInfo {
+            label: "ship it $deadline=2026-08-01".to_string(),
+            tags: vec![],
+            variables: vec![Variable::new(
+                "deadline",
+                VariableValue::date(Date {
+                    year: 2026,
+                    month: 8,
+                    day: 1,
+                    hour: None,
+                    minute: None,
+                }),
+            )],
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn it_infers_date_time_variables() {
+        use crate::graph::Date;
+
+        let actual = NodeInfo::parse(&Label(
+            "ship it $deadline=2026-08-01T09:30".to_string(),
+        ));
+        let expected = NodeInfo {
+            label: "ship it $deadline=2026-08-01T09:30".to_string(),
+            tags: vec![],
+            variables: vec![Variable::new(
+                "deadline",
+                VariableValue::date(Date {
+                    year: 2026,
+                    month: 8,
+                    day: 1,
+                    hour: Some(9),
+                    minute: Some(30),
+                }),
+            )],
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn it_falls_back_to_string_for_impossible_dates() {
+        let variables = vec!["2026-13-01", "2026-02-30", "2025-02-29", "2026-00-10"];
+        for value in variables {
+            let actual = NodeInfo::parse(&Label(format!("$bad={}", value)));
+            assert_eq!(
+                actual.variables,
+                vec![Variable::new("bad", VariableValue::string(value))],
+                "expected {} to fall back to a string",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn it_leaves_tags_unchanged_with_no_tag_config() {
+        let actual = NodeInfo::parse(&Label("a #TODO tag".to_string()));
+        let expected = NodeInfo {
+            label: "a #TODO tag".to_string(),
+            tags: vec![HashTag::new("#TODO")],
+            variables: Vec::new(),
+            subgraph: None,
+        };
+        assert_eq!(actual, expected);
+    }
 }
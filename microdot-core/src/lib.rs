@@ -0,0 +1,16 @@
+pub mod graph;
+pub mod hash;
+pub mod labels;
+
+use std::fmt;
+
+/// The raw text a user typed for a node, before tags/variables are
+/// extracted from it by [`labels::NodeInfo::parse`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label(pub String);
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}